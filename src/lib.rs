@@ -1,23 +1,53 @@
 #![cfg_attr(not(feature = "export-abi"), no_main)]
 extern crate alloc;
 
+use alloc::string::String;
 use stylus_sdk::{
     prelude::*,
-    alloy_primitives::{Address, U256},
-    storage::StorageAddress,
+    alloy_primitives::{address, Address, FixedBytes, U256},
+    call::Call,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageU256},
     crypto,
 };
 use alloy_sol_types::{sol, SolEvent};
 
+// ERC-165 interface IDs this contract answers true for.
+const ERC165_INTERFACE_ID: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+const ERC20_INTERFACE_ID: [u8; 4] = [0x36, 0x37, 0x2b, 0x07];
+
+const TOKEN_NAME: &str = "Stylus Primitives Demo Token";
+const TOKEN_SYMBOL: &str = "SPD";
+const TOKEN_DECIMALS: u8 = 18;
+
+// Precompile address for ECDSA public key recovery (EIP-2).
+const ECRECOVER_ADDRESS: Address = address!("0000000000000000000000000000000000000001");
+// Precompile address for SHA-256 (EIP-2).
+const SHA256_ADDRESS: Address = address!("0000000000000000000000000000000000000002");
+// Precompile address for RIPEMD-160 (EIP-2).
+const RIPEMD160_ADDRESS: Address = address!("0000000000000000000000000000000000000003");
+// Precompile address for the BLAKE2 compression function F (EIP-152).
+const BLAKE2F_ADDRESS: Address = address!("0000000000000000000000000000000000000009");
+
 // Define your event using alloy_sol_types
 sol! {
     event EmitMe(address indexed sender, uint256 value);
+    event ReceiptRedeemed(address indexed redeemer, bytes32 indexed receipt_id);
+    event Redeemed(address indexed from, bytes32 indexed receipt_id, uint256 indexed amount, string memo);
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
 }
 
 #[storage]
 #[entrypoint]
 pub struct StylusPrimitivesDemo {
     owner: StorageAddress,
+    // Tracks receipt hashes that have already been redeemed, so a signed
+    // receipt can't be replayed across multiple calls.
+    used_receipts: StorageMap<FixedBytes<32>, StorageBool>,
+    // ERC-20 balances, allowances, and total supply (optional token mode).
+    balances: StorageMap<Address, StorageU256>,
+    allowances: StorageMap<Address, StorageMap<Address, StorageU256>>,
+    total_supply: StorageU256,
 }
 
 #[public]
@@ -53,29 +83,381 @@ impl StylusPrimitivesDemo {
     pub fn emit_my_event(&self) {
         let sender = self.vm().msg_sender();
         let value = self.vm().msg_value();
-        
-        let event = EmitMe {
-            sender,
-            value,
-        };
-        
-        // Encode the event properly
-        let mut topics = vec![];
-        event.encode_topics_raw(&mut topics).expect("failed to encode topics");
-        
-        let data = event.encode_data();
-        
-        // Emit with correct format
-        self.vm().emit_log(&data, topics.len());
+
+        self.emit_event(EmitMe { sender, value });
     }
-    
+
     // 8. Return keccak256 hash of preimage
     pub fn hash_preimage(&self, preimage: Vec<u8>) -> [u8; 32] {
         crypto::keccak(&preimage).into()
     }
-    
+
+    // 8a. Return the SHA-256 hash of preimage, via the SHA-256 precompile.
+    pub fn sha256_preimage(&self, preimage: Vec<u8>) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(Call::new(), SHA256_ADDRESS, &preimage)
+    }
+
+    // 8b. Return the RIPEMD-160 hash of preimage, via the RIPEMD-160 precompile.
+    pub fn ripemd160_preimage(&self, preimage: Vec<u8>) -> Result<Vec<u8>, Vec<u8>> {
+        self.vm().call(Call::new(), RIPEMD160_ADDRESS, &preimage)
+    }
+
+    // 8c. Run the BLAKE2b compression function F over `input`, via the
+    // BLAKE2F precompile. `input` must be the exact 213-byte payload laid
+    // out as: rounds (4 bytes, big-endian) || h (64 bytes) || m (128 bytes)
+    // || t0 (8 bytes, little-endian) || t1 (8 bytes, little-endian) ||
+    // final flag (1 byte, must be 0 or 1). Returns the resulting 64-byte
+    // state `h`.
+    pub fn blake2f_preimage(&self, input: Vec<u8>) -> Result<Vec<u8>, Vec<u8>> {
+        if input.len() != 213 {
+            return Err(b"blake2f input must be 213 bytes".to_vec());
+        }
+        if input[212] > 1 {
+            return Err(b"blake2f final flag must be 0 or 1".to_vec());
+        }
+
+        self.vm().call(Call::new(), BLAKE2F_ADDRESS, &input)
+    }
+
     // Helper: Get owner
     pub fn get_owner(&self) -> Address {
         self.owner.get()
     }
+
+    // 9. Verify a signature over `message_hash` was produced by the owner.
+    //
+    // When `eth_signed_message` is set, `message_hash` is re-hashed with the
+    // EIP-191 prefix first so signatures from wallet `personal_sign` (which
+    // prefix the message before signing) verify correctly too.
+    pub fn verify_owner_signature(
+        &self,
+        message_hash: [u8; 32],
+        signature: Vec<u8>,
+        eth_signed_message: bool,
+    ) -> bool {
+        let hash = if eth_signed_message {
+            eth_signed_message_hash(message_hash)
+        } else {
+            message_hash
+        };
+
+        match self.recover_signer(hash, &signature) {
+            Some(signer) => signer == self.owner.get(),
+            None => false,
+        }
+    }
+
+    // Internal: recover the signer address of `hash` from a 65-byte
+    // `r || s || v` signature via the ecrecover precompile.
+    fn recover_signer(&self, hash: [u8; 32], signature: &[u8]) -> Option<Address> {
+        if signature.len() != 65 {
+            return None;
+        }
+
+        let mut v = signature[64];
+        if v < 27 {
+            v += 27;
+        }
+        if v != 27 && v != 28 {
+            return None;
+        }
+
+        // ecrecover input: hash (32) || v as 32-byte big-endian (32) || r (32) || s (32)
+        let mut input = [0u8; 128];
+        input[0..32].copy_from_slice(&hash);
+        input[63] = v;
+        input[64..96].copy_from_slice(&signature[0..32]);
+        input[96..128].copy_from_slice(&signature[32..64]);
+
+        let result = self.vm().call(Call::new(), ECRECOVER_ADDRESS, &input).ok()?;
+        if result.len() != 32 || result[..12] != [0u8; 12] {
+            return None;
+        }
+
+        let signer = Address::from_slice(&result[12..32]);
+        if signer.is_zero() {
+            None
+        } else {
+            Some(signer)
+        }
+    }
+
+    // 10. Redeem a single-use receipt signed by the owner.
+    //
+    // `receipt` is an opaque, application-defined payload (e.g. an encoded
+    // mint voucher); `sig` must be the owner's signature over its keccak256
+    // hash. Each receipt hash may only be redeemed once, which is what
+    // prevents the classic bridge bug of replaying the same receipt.
+    pub fn redeem_receipt(&mut self, receipt: Vec<u8>, sig: Vec<u8>) -> Result<(), Vec<u8>> {
+        let id = FixedBytes::<32>::from(crypto::keccak(&receipt));
+
+        if self.used_receipts.get(id) {
+            return Err(b"receipt already redeemed".to_vec());
+        }
+
+        let signer = self
+            .recover_signer(id.into(), &sig)
+            .ok_or_else(|| b"invalid signature".to_vec())?;
+        if signer != self.owner.get() {
+            return Err(b"signature not from owner".to_vec());
+        }
+
+        self.used_receipts.setter(id).set(true);
+
+        let redeemer = self.vm().msg_sender();
+        self.emit_event(ReceiptRedeemed {
+            redeemer,
+            receipt_id: id,
+        });
+
+        Ok(())
+    }
+
+    // 11. Emit a Redeemed event with three indexed topics (from, receiptId,
+    // amount) plus a non-indexed `memo`, so indexers can filter by any of
+    // them.
+    pub fn emit_redeemed(&mut self, receipt_id: [u8; 32], amount: U256, memo: String) {
+        let from = self.vm().msg_sender();
+        self.emit_event(Redeemed {
+            from,
+            receipt_id: FixedBytes::from(receipt_id),
+            amount,
+            memo,
+        });
+    }
+
+    // 12. Emit an anonymous log with caller-supplied topics and data,
+    // bypassing the `sol!`-typed event path entirely. EVM logs carry at
+    // most four topics.
+    pub fn raw_log(&self, topics: Vec<[u8; 32]>, data: Vec<u8>) -> Result<(), Vec<u8>> {
+        if topics.len() > 4 {
+            return Err(b"at most 4 topics are allowed".to_vec());
+        }
+
+        let mut buf = Vec::with_capacity(topics.len() * 32 + data.len());
+        for topic in &topics {
+            buf.extend_from_slice(topic);
+        }
+        buf.extend_from_slice(&data);
+
+        self.vm().emit_log(&buf, topics.len());
+        Ok(())
+    }
+
+    // Internal: ABI-encode and emit a `sol!`-defined event, laying out the
+    // log as `[topic0 (keccak of the event signature), ...indexed topics]`
+    // followed by the ABI-encoded non-indexed fields.
+    fn emit_event<E: SolEvent>(&self, event: E) {
+        let topics = event.encode_topics();
+
+        let data = event.encode_data();
+        let mut buf = Vec::with_capacity(topics.len() * 32 + data.len());
+        for topic in &topics {
+            buf.extend_from_slice(topic.as_slice());
+        }
+        buf.extend_from_slice(&data);
+
+        self.vm().emit_log(&buf, topics.len());
+    }
+
+    // 13. ERC-20 metadata.
+    pub fn name(&self) -> String {
+        TOKEN_NAME.into()
+    }
+
+    pub fn symbol(&self) -> String {
+        TOKEN_SYMBOL.into()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        TOKEN_DECIMALS
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    pub fn balance_of(&self, account: Address) -> U256 {
+        self.balances.get(account)
+    }
+
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
+
+    // 14. Mint new tokens to `to`. Restricted to the contract owner.
+    pub fn mint(&mut self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        if self.vm().msg_sender() != self.owner.get() {
+            return Err(b"caller is not the owner".to_vec());
+        }
+
+        let total_supply = self
+            .total_supply
+            .get()
+            .checked_add(amount)
+            .ok_or_else(|| b"total supply overflow".to_vec())?;
+        let balance = self
+            .balances
+            .get(to)
+            .checked_add(amount)
+            .ok_or_else(|| b"balance overflow".to_vec())?;
+
+        self.total_supply.set(total_supply);
+        self.balances.setter(to).set(balance);
+
+        self.emit_event(Transfer {
+            from: Address::ZERO,
+            to,
+            value: amount,
+        });
+
+        Ok(())
+    }
+
+    // 15. Move `amount` tokens from the caller to `to`.
+    pub fn transfer(&mut self, to: Address, amount: U256) -> Result<bool, Vec<u8>> {
+        let from = self.vm().msg_sender();
+        self.move_balance(from, to, amount)?;
+
+        self.emit_event(Transfer { from, to, value: amount });
+        Ok(true)
+    }
+
+    // 16. Approve `spender` to transfer up to `amount` tokens on the
+    // caller's behalf.
+    pub fn approve(&mut self, spender: Address, amount: U256) -> Result<bool, Vec<u8>> {
+        let owner = self.vm().msg_sender();
+        self.allowances.setter(owner).setter(spender).set(amount);
+
+        self.emit_event(Approval { owner, spender, value: amount });
+        Ok(true)
+    }
+
+    // 17. Move `amount` tokens from `from` to `to`, spending the caller's
+    // allowance from `from`.
+    pub fn transfer_from(&mut self, from: Address, to: Address, amount: U256) -> Result<bool, Vec<u8>> {
+        let spender = self.vm().msg_sender();
+        let allowance = self.allowances.getter(from).get(spender);
+        let remaining = allowance
+            .checked_sub(amount)
+            .ok_or_else(|| b"insufficient allowance".to_vec())?;
+
+        self.move_balance(from, to, amount)?;
+        self.allowances.setter(from).setter(spender).set(remaining);
+
+        self.emit_event(Transfer { from, to, value: amount });
+        Ok(true)
+    }
+
+    // 18. ERC-165: report support for the ERC-20 and ERC-165 interfaces.
+    pub fn supports_interface(&self, id: [u8; 4]) -> bool {
+        id == ERC165_INTERFACE_ID || id == ERC20_INTERFACE_ID
+    }
+
+    // Internal: move `amount` tokens from `from` to `to`, reverting on
+    // insufficient balance or overflow.
+    //
+    // The debit is read, computed, and written before the credit side is
+    // ever read, so a self-transfer (`from == to`) nets to zero instead of
+    // having its own debit clobbered by a credit computed from the
+    // pre-debit balance.
+    fn move_balance(&mut self, from: Address, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        let from_balance = self
+            .balances
+            .get(from)
+            .checked_sub(amount)
+            .ok_or_else(|| b"insufficient balance".to_vec())?;
+        self.balances.setter(from).set(from_balance);
+
+        let to_balance = self
+            .balances
+            .get(to)
+            .checked_add(amount)
+            .ok_or_else(|| b"balance overflow".to_vec())?;
+        self.balances.setter(to).set(to_balance);
+
+        Ok(())
+    }
+}
+
+// Hash `message_hash` with the EIP-191 "Ethereum Signed Message" prefix, as
+// applied by wallets before signing with `personal_sign`.
+fn eth_signed_message_hash(message_hash: [u8; 32]) -> [u8; 32] {
+    let mut preimage = Vec::with_capacity(28 + 32);
+    preimage.extend_from_slice(b"\x19Ethereum Signed Message:\n32");
+    preimage.extend_from_slice(&message_hash);
+    crypto::keccak(&preimage).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stylus_sdk::testing::*;
+
+    fn deploy(vm: &TestVM, owner: Address) -> StylusPrimitivesDemo {
+        vm.set_sender(owner);
+        let mut contract = StylusPrimitivesDemo::from(vm);
+        contract.initialize();
+        contract
+    }
+
+    #[test]
+    fn transfer_moves_balance() {
+        let vm = TestVM::default();
+        let owner = Address::from([1u8; 20]);
+        let recipient = Address::from([2u8; 20]);
+        let mut contract = deploy(&vm, owner);
+
+        vm.set_sender(owner);
+        contract.mint(owner, U256::from(100)).unwrap();
+        contract.transfer(recipient, U256::from(40)).unwrap();
+
+        assert_eq!(contract.balance_of(owner), U256::from(60));
+        assert_eq!(contract.balance_of(recipient), U256::from(40));
+        assert_eq!(contract.total_supply(), U256::from(100));
+    }
+
+    #[test]
+    fn self_transfer_preserves_balance_and_supply() {
+        let vm = TestVM::default();
+        let owner = Address::from([1u8; 20]);
+        let mut contract = deploy(&vm, owner);
+
+        vm.set_sender(owner);
+        contract.mint(owner, U256::from(100)).unwrap();
+        contract.transfer(owner, U256::from(30)).unwrap();
+
+        assert_eq!(contract.balance_of(owner), U256::from(100));
+        assert_eq!(contract.total_supply(), U256::from(100));
+    }
+
+    #[test]
+    fn transfer_reverts_on_insufficient_balance() {
+        let vm = TestVM::default();
+        let owner = Address::from([1u8; 20]);
+        let recipient = Address::from([2u8; 20]);
+        let mut contract = deploy(&vm, owner);
+
+        vm.set_sender(owner);
+        contract.mint(owner, U256::from(10)).unwrap();
+
+        assert!(contract.transfer(recipient, U256::from(20)).is_err());
+    }
+
+    #[test]
+    fn transfer_from_reverts_on_insufficient_allowance() {
+        let vm = TestVM::default();
+        let owner = Address::from([1u8; 20]);
+        let spender = Address::from([2u8; 20]);
+        let recipient = Address::from([3u8; 20]);
+        let mut contract = deploy(&vm, owner);
+
+        vm.set_sender(owner);
+        contract.mint(owner, U256::from(100)).unwrap();
+
+        vm.set_sender(spender);
+        assert!(contract
+            .transfer_from(owner, recipient, U256::from(10))
+            .is_err());
+    }
 }
\ No newline at end of file